@@ -0,0 +1,592 @@
+#![no_std]
+
+mod test;
+
+use soroban_sdk::{contract, contractimpl, contracttype, symbol_short, token, Address, Env, Symbol, Vec};
+
+/// 1 USDC (6-decimal) minimum deposit floor used by `initialize`.
+const DEFAULT_MIN_DEPOSIT: i128 = 1_000_000;
+/// 10K USDC default per-transaction ceiling used by `initialize`.
+const DEFAULT_MAX_DEPOSIT: i128 = 10_000_000_000;
+/// Effectively unlimited until the owner sets a real cap via
+/// `set_total_deposit_cap`.
+const DEFAULT_TOTAL_DEPOSIT_CAP: i128 = i128::MAX;
+/// Effectively unlimited until the owner sets a real limit via
+/// `set_withdrawal_limit`.
+const DEFAULT_WITHDRAWAL_LIMIT_PER_WINDOW: i128 = i128::MAX;
+/// One day, in seconds; the default rolling window until the owner sets a
+/// tighter one via `set_withdrawal_limit`.
+const DEFAULT_WITHDRAWAL_WINDOW_SECONDS: u64 = 86_400;
+
+#[contracttype]
+#[derive(Clone)]
+pub enum DataKey {
+    Agent,
+    Owner,
+    UsdcToken,
+    MinDeposit,
+    MaxDeposit,
+    EmergencyOwner,
+    DepositsPaused,
+    WithdrawalsPaused,
+    TotalDepositCap,
+    TotalDeposited,
+    TotalShares,
+    Shares(Address),
+    Depositors,
+    KnownDepositor(Address),
+    WithdrawalLimitPerWindow,
+    WithdrawalWindowSeconds,
+    WithdrawalWindow(Address),
+}
+
+/// A user's net-outflow accumulator for the rolling withdrawal window it
+/// was last touched in. `window_index` is `timestamp / window_seconds`;
+/// once the current window's index moves past it, `accumulated` resets
+/// rather than carrying over.
+#[contracttype]
+#[derive(Clone)]
+pub struct WithdrawalWindow {
+    pub window_index: u64,
+    pub accumulated: i128,
+}
+
+#[contract]
+pub struct NeuroWealthVault;
+
+#[contractimpl]
+impl NeuroWealthVault {
+    pub fn initialize(env: Env, agent: Address, usdc_token: Address) {
+        env.storage().instance().set(&DataKey::Agent, &agent);
+        env.storage().instance().set(&DataKey::Owner, &agent);
+        env.storage().instance().set(&DataKey::UsdcToken, &usdc_token);
+        env.storage()
+            .instance()
+            .set(&DataKey::MinDeposit, &DEFAULT_MIN_DEPOSIT);
+        env.storage()
+            .instance()
+            .set(&DataKey::MaxDeposit, &DEFAULT_MAX_DEPOSIT);
+
+        // The agent doubles as the initial emergency owner so the same key
+        // that bootstraps the vault can freeze it if something goes wrong
+        // before a dedicated guardian is assigned.
+        env.storage().instance().set(&DataKey::EmergencyOwner, &agent);
+        env.storage().instance().set(&DataKey::DepositsPaused, &false);
+        env.storage()
+            .instance()
+            .set(&DataKey::WithdrawalsPaused, &false);
+
+        env.storage()
+            .instance()
+            .set(&DataKey::TotalDepositCap, &DEFAULT_TOTAL_DEPOSIT_CAP);
+        env.storage().instance().set(&DataKey::TotalDeposited, &0_i128);
+        env.storage().instance().set(&DataKey::TotalShares, &0_i128);
+
+        env.storage().instance().set(
+            &DataKey::WithdrawalLimitPerWindow,
+            &DEFAULT_WITHDRAWAL_LIMIT_PER_WINDOW,
+        );
+        env.storage().instance().set(
+            &DataKey::WithdrawalWindowSeconds,
+            &DEFAULT_WITHDRAWAL_WINDOW_SECONDS,
+        );
+    }
+
+    pub fn get_min_deposit(env: Env) -> i128 {
+        env.storage().instance().get(&DataKey::MinDeposit).unwrap()
+    }
+
+    /// Effective remaining headroom for `receiver`: the smaller of the
+    /// per-transaction ceiling and the room left under the vault-wide cap,
+    /// or zero if depositing `amount` right now would be a lossy deposit.
+    /// `receiver` is currently unused but kept in the signature for
+    /// future per-receiver adjustments.
+    pub fn get_max_deposit(env: Env, _receiver: Address, amount: i128) -> i128 {
+        if amount > 0 && Self::is_lossy_deposit(&env, amount) {
+            return 0;
+        }
+
+        let per_tx_max: i128 = env.storage().instance().get(&DataKey::MaxDeposit).unwrap();
+        let cap: i128 = env.storage().instance().get(&DataKey::TotalDepositCap).unwrap();
+        let total_deposited: i128 =
+            env.storage().instance().get(&DataKey::TotalDeposited).unwrap();
+
+        let headroom = (cap - total_deposited).max(0);
+        per_tx_max.min(headroom)
+    }
+
+    /// Assets currently held by the vault, i.e. the real USDC balance —
+    /// distinct from `total_deposited`, which only tracks outstanding
+    /// principal for the TVL cap and doesn't move with yield or loss.
+    pub fn total_assets(env: Env) -> i128 {
+        let usdc_token: Address = env.storage().instance().get(&DataKey::UsdcToken).unwrap();
+        let token_client = token::Client::new(&env, &usdc_token);
+        token_client.balance(&env.current_contract_address())
+    }
+
+    /// Shares a deposit of `amount` would mint at the current price,
+    /// minting 1:1 while the vault is empty.
+    pub fn convert_to_shares(env: Env, amount: i128) -> i128 {
+        let total_assets = Self::total_assets(env.clone());
+        Self::shares_at(&env, amount, total_assets)
+    }
+
+    /// Assets `shares` would redeem for at the current price.
+    pub fn convert_to_assets(env: Env, shares: i128) -> i128 {
+        let total_assets = Self::total_assets(env.clone());
+        Self::assets_at(&env, shares, total_assets)
+    }
+
+    /// Like `convert_to_shares`, but priced against a caller-supplied
+    /// asset total instead of the vault's current real balance. Needed by
+    /// `on_deposit_received`, where the transfer has already landed, to
+    /// price against the balance *before* this deposit arrived.
+    fn shares_at(env: &Env, amount: i128, total_assets: i128) -> i128 {
+        let total_shares: i128 = env.storage().instance().get(&DataKey::TotalShares).unwrap();
+        if total_shares == 0 || total_assets <= 0 {
+            amount
+        } else {
+            amount * total_shares / total_assets
+        }
+    }
+
+    /// Like `convert_to_assets`, but priced against a caller-supplied
+    /// asset total. See `shares_at`.
+    fn assets_at(env: &Env, shares: i128, total_assets: i128) -> i128 {
+        let total_shares: i128 = env.storage().instance().get(&DataKey::TotalShares).unwrap();
+        if total_shares == 0 || total_assets <= 0 {
+            shares
+        } else {
+            shares * total_assets / total_shares
+        }
+    }
+
+    pub fn balance_of(env: Env, user: Address) -> i128 {
+        env.storage()
+            .instance()
+            .get(&DataKey::Shares(user))
+            .unwrap_or(0)
+    }
+
+    /// Side-effect-free self-check: panics with a descriptive message if
+    /// the vault's internal state has drifted into an impossible
+    /// configuration, so auditors and CI can call it after any sequence
+    /// of deposits/withdrawals/limit changes to confirm consistency.
+    pub fn verify_invariants(env: Env) {
+        let min_deposit = Self::get_min_deposit(env.clone());
+        if min_deposit < DEFAULT_MIN_DEPOSIT {
+            panic!("Invariant violated: min_deposit below the 1 USDC floor");
+        }
+
+        let max_deposit: i128 = env.storage().instance().get(&DataKey::MaxDeposit).unwrap();
+        if max_deposit < min_deposit {
+            panic!("Invariant violated: max_deposit below min_deposit");
+        }
+
+        let total_deposited: i128 =
+            env.storage().instance().get(&DataKey::TotalDeposited).unwrap();
+        let cap: i128 = env.storage().instance().get(&DataKey::TotalDepositCap).unwrap();
+        if total_deposited > cap {
+            panic!("Invariant violated: total_deposited exceeds total_deposit_cap");
+        }
+
+        let total_assets = Self::total_assets(env.clone());
+        if total_assets < 0 {
+            panic!("Invariant violated: total_assets is negative");
+        }
+
+        let total_shares: i128 = env.storage().instance().get(&DataKey::TotalShares).unwrap();
+        if (total_shares == 0) != (total_assets == 0) {
+            panic!("Invariant violated: total_shares and total_assets must be zero together");
+        }
+
+        let depositors: Vec<Address> = env
+            .storage()
+            .instance()
+            .get(&DataKey::Depositors)
+            .unwrap_or_else(|| Vec::new(&env));
+        let mut sum_of_balances: i128 = 0;
+        for depositor in depositors.iter() {
+            sum_of_balances += Self::balance_of(env.clone(), depositor);
+        }
+        if sum_of_balances != total_shares {
+            panic!("Invariant violated: sum of share balances does not equal total_shares");
+        }
+    }
+
+    pub fn set_deposit_limits(env: Env, min_deposit: i128, max_deposit: i128) {
+        Self::assert_owner(&env);
+
+        if min_deposit < DEFAULT_MIN_DEPOSIT {
+            panic!("Minimum deposit must be at least 1 USDC");
+        }
+        if max_deposit < min_deposit {
+            panic!("Maximum deposit must be greater than or equal to minimum");
+        }
+
+        env.storage().instance().set(&DataKey::MinDeposit, &min_deposit);
+        env.storage().instance().set(&DataKey::MaxDeposit, &max_deposit);
+    }
+
+    /// Owner-guarded, analogous to `set_deposit_limits`: caps net
+    /// withdrawals per account to `limit` within each fixed
+    /// `window_seconds`-wide window (`timestamp / window_seconds`); the
+    /// allowance resets at each window boundary rather than sliding.
+    pub fn set_withdrawal_limit(env: Env, limit: i128, window_seconds: u64) {
+        Self::assert_owner(&env);
+
+        if limit < 0 {
+            panic!("Withdrawal limit must be non-negative");
+        }
+        if window_seconds == 0 {
+            panic!("Window seconds must be greater than zero");
+        }
+
+        env.storage()
+            .instance()
+            .set(&DataKey::WithdrawalLimitPerWindow, &limit);
+        env.storage()
+            .instance()
+            .set(&DataKey::WithdrawalWindowSeconds, &window_seconds);
+    }
+
+    pub fn get_withdrawal_limit_per_window(env: Env) -> i128 {
+        env.storage()
+            .instance()
+            .get(&DataKey::WithdrawalLimitPerWindow)
+            .unwrap()
+    }
+
+    pub fn get_withdrawal_window_seconds(env: Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&DataKey::WithdrawalWindowSeconds)
+            .unwrap()
+    }
+
+    /// Deposits `amount` USDC on behalf of `user` and mints the
+    /// corresponding shares, returning the number minted.
+    pub fn deposit(env: Env, user: Address, amount: i128) -> i128 {
+        user.require_auth();
+
+        if Self::is_deposits_paused(env.clone()) {
+            panic!("Deposits paused");
+        }
+
+        let min_deposit = Self::get_min_deposit(env.clone());
+        let max_deposit: i128 = env.storage().instance().get(&DataKey::MaxDeposit).unwrap();
+        if amount < min_deposit {
+            panic!("Below minimum deposit");
+        }
+        if amount > max_deposit {
+            panic!("Exceeds maximum deposit");
+        }
+
+        Self::enforce_total_deposit_cap(&env, amount);
+
+        if Self::is_lossy_deposit(&env, amount) {
+            panic!("Lossy deposit");
+        }
+
+        let usdc_token: Address = env.storage().instance().get(&DataKey::UsdcToken).unwrap();
+        let token_client = token::Client::new(&env, &usdc_token);
+        token_client.transfer(&user, &env.current_contract_address(), &amount);
+
+        // Priced from the pre-deposit balance, before the transfer above
+        // changed it.
+        Self::credit_deposit(&env, &user, amount, Self::total_assets(env.clone()) - amount)
+    }
+
+    /// One-step deposit: the USDC token contract calls this after
+    /// transferring `amount` into the vault on `from`'s behalf, so a
+    /// single token transfer both moves funds and credits the depositor.
+    /// Mirrors the reserve-then-refund-remainder resolve-transfer
+    /// pattern: a caller other than the configured USDC token panics; on
+    /// any other validation failure (or when `msg` asks for it) the
+    /// unused amount is returned so the token contract can reverse it.
+    pub fn on_deposit_received(env: Env, caller: Address, from: Address, amount: i128, msg: Symbol) -> i128 {
+        caller.require_auth();
+        let usdc_token: Address = env.storage().instance().get(&DataKey::UsdcToken).unwrap();
+        if caller != usdc_token {
+            panic!("Caller must be the configured USDC token");
+        }
+
+        if msg == symbol_short!("refund") {
+            return amount;
+        }
+
+        if Self::is_deposits_paused(env.clone()) {
+            return amount;
+        }
+
+        let min_deposit = Self::get_min_deposit(env.clone());
+        let max_deposit: i128 = env.storage().instance().get(&DataKey::MaxDeposit).unwrap();
+        if amount < min_deposit || amount > max_deposit {
+            return amount;
+        }
+
+        if Self::exceeds_total_deposit_cap(&env, amount) {
+            return amount;
+        }
+
+        // The tokens already landed in the vault's balance, so back
+        // `amount` out to price shares against the pre-deposit balance.
+        let total_assets_before = Self::total_assets(env.clone()) - amount;
+        let shares = Self::shares_at(&env, amount, total_assets_before);
+        if Self::assets_at(&env, shares, total_assets_before) < amount {
+            return amount;
+        }
+
+        Self::credit_deposit(&env, &from, amount, total_assets_before);
+        0
+    }
+
+    /// Records a successful deposit of `amount` from `user`, minting
+    /// shares priced against `total_assets_before` (the vault's asset
+    /// total immediately before this deposit's funds arrived). Returns
+    /// the number of shares minted.
+    fn credit_deposit(env: &Env, user: &Address, amount: i128, total_assets_before: i128) -> i128 {
+        let shares = Self::shares_at(env, amount, total_assets_before);
+
+        let total_deposited: i128 =
+            env.storage().instance().get(&DataKey::TotalDeposited).unwrap();
+        env.storage()
+            .instance()
+            .set(&DataKey::TotalDeposited, &(total_deposited + amount));
+
+        let total_shares: i128 = env.storage().instance().get(&DataKey::TotalShares).unwrap();
+        env.storage()
+            .instance()
+            .set(&DataKey::TotalShares, &(total_shares + shares));
+
+        let user_shares = Self::balance_of(env.clone(), user.clone());
+        env.storage()
+            .instance()
+            .set(&DataKey::Shares(user.clone()), &(user_shares + shares));
+
+        // Track first-time depositors so `verify_invariants` can sum every
+        // per-user balance without needing to enumerate storage directly.
+        if !env.storage().instance().has(&DataKey::KnownDepositor(user.clone())) {
+            env.storage()
+                .instance()
+                .set(&DataKey::KnownDepositor(user.clone()), &true);
+            let mut depositors: Vec<Address> = env
+                .storage()
+                .instance()
+                .get(&DataKey::Depositors)
+                .unwrap_or_else(|| Vec::new(env));
+            depositors.push_back(user.clone());
+            env.storage().instance().set(&DataKey::Depositors, &depositors);
+        }
+
+        shares
+    }
+
+    /// Burns `shares` on behalf of `user` and returns the proportional
+    /// asset amount.
+    pub fn redeem(env: Env, user: Address, shares: i128) -> i128 {
+        user.require_auth();
+
+        if shares <= 0 {
+            panic!("Shares must be positive");
+        }
+
+        if Self::is_withdrawals_paused(env.clone()) {
+            panic!("Withdrawals paused");
+        }
+
+        let user_shares = Self::balance_of(env.clone(), user.clone());
+        if shares > user_shares {
+            panic!("Insufficient shares");
+        }
+
+        let assets = Self::convert_to_assets(env.clone(), shares);
+
+        Self::enforce_withdrawal_rate_limit(&env, &user, assets);
+
+        env.storage()
+            .instance()
+            .set(&DataKey::Shares(user.clone()), &(user_shares - shares));
+
+        let total_shares: i128 = env.storage().instance().get(&DataKey::TotalShares).unwrap();
+        env.storage()
+            .instance()
+            .set(&DataKey::TotalShares, &(total_shares - shares));
+
+        // Decrement total_deposited by the *principal* these shares
+        // represent, not by `assets` (the post-yield payout) — otherwise a
+        // redemption above par drives total_deposited negative and blows
+        // open the TVL cap for the next depositor.
+        let total_deposited: i128 =
+            env.storage().instance().get(&DataKey::TotalDeposited).unwrap();
+        let principal = if total_shares == 0 {
+            0
+        } else {
+            (total_deposited * shares / total_shares).min(total_deposited)
+        };
+        env.storage()
+            .instance()
+            .set(&DataKey::TotalDeposited, &(total_deposited - principal).max(0));
+
+        let usdc_token: Address = env.storage().instance().get(&DataKey::UsdcToken).unwrap();
+        let token_client = token::Client::new(&env, &usdc_token);
+        token_client.transfer(&env.current_contract_address(), &user, &assets);
+
+        assets
+    }
+
+    /// Owner-guarded, analogous to `set_deposit_limits`.
+    pub fn set_total_deposit_cap(env: Env, total_deposit_cap: i128) {
+        Self::assert_owner(&env);
+
+        if total_deposit_cap < 0 {
+            panic!("Total deposit cap must be non-negative");
+        }
+
+        env.storage()
+            .instance()
+            .set(&DataKey::TotalDepositCap, &total_deposit_cap);
+    }
+
+    /// Reassigns the emergency owner. Owner-guarded so the emergency role
+    /// can be rotated or revoked without redeploying the vault.
+    pub fn set_emergency_owner(env: Env, new_emergency_owner: Address) {
+        Self::assert_owner(&env);
+        env.storage()
+            .instance()
+            .set(&DataKey::EmergencyOwner, &new_emergency_owner);
+    }
+
+    /// Freezes deposits. Does not touch limits, balances, or withdrawals.
+    pub fn pause_deposits(env: Env, caller: Address) {
+        Self::assert_emergency_owner(&env, &caller);
+        env.storage().instance().set(&DataKey::DepositsPaused, &true);
+    }
+
+    /// Freezes withdrawals. Does not touch limits, balances, or deposits.
+    pub fn pause_withdrawals(env: Env, caller: Address) {
+        Self::assert_emergency_owner(&env, &caller);
+        env.storage()
+            .instance()
+            .set(&DataKey::WithdrawalsPaused, &true);
+    }
+
+    /// Clears both pause flags, restoring normal operation.
+    pub fn resume(env: Env, caller: Address) {
+        Self::assert_emergency_owner(&env, &caller);
+        env.storage().instance().set(&DataKey::DepositsPaused, &false);
+        env.storage()
+            .instance()
+            .set(&DataKey::WithdrawalsPaused, &false);
+    }
+
+    pub fn get_emergency_owner(env: Env) -> Address {
+        env.storage().instance().get(&DataKey::EmergencyOwner).unwrap()
+    }
+
+    pub fn is_deposits_paused(env: Env) -> bool {
+        env.storage()
+            .instance()
+            .get(&DataKey::DepositsPaused)
+            .unwrap_or(false)
+    }
+
+    pub fn is_withdrawals_paused(env: Env) -> bool {
+        env.storage()
+            .instance()
+            .get(&DataKey::WithdrawalsPaused)
+            .unwrap_or(false)
+    }
+
+    /// Rejects a balance-increasing action that would push
+    /// `total_deposited` past `total_deposit_cap`. Actions that do not
+    /// increase total assets (e.g. a no-op re-deposit or an internal
+    /// rebalance where `delta <= 0`) skip the check entirely, so the vault
+    /// never blocks flows that don't actually grow exposure once it's at
+    /// capacity.
+    fn enforce_total_deposit_cap(env: &Env, delta: i128) {
+        if Self::exceeds_total_deposit_cap(env, delta) {
+            panic!("Exceeds vault capacity");
+        }
+    }
+
+    /// Non-panicking form of `enforce_total_deposit_cap`, for callers
+    /// (like `on_deposit_received`) that need to refund instead of panic.
+    fn exceeds_total_deposit_cap(env: &Env, delta: i128) -> bool {
+        if delta <= 0 {
+            return false;
+        }
+
+        let cap: i128 = env.storage().instance().get(&DataKey::TotalDepositCap).unwrap();
+        let total_deposited: i128 =
+            env.storage().instance().get(&DataKey::TotalDeposited).unwrap();
+
+        total_deposited + delta > cap
+    }
+
+    /// Folds `assets` into `user`'s net-outflow accumulator for the
+    /// current rolling window (`timestamp / window_seconds`), resetting it
+    /// first if the window has rolled over since their last withdrawal,
+    /// then panics if the running total would exceed the configured limit.
+    fn enforce_withdrawal_rate_limit(env: &Env, user: &Address, assets: i128) {
+        let limit: i128 = env
+            .storage()
+            .instance()
+            .get(&DataKey::WithdrawalLimitPerWindow)
+            .unwrap();
+        let window_seconds: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::WithdrawalWindowSeconds)
+            .unwrap();
+        let current_index = env.ledger().timestamp() / window_seconds;
+
+        let window: Option<WithdrawalWindow> = env
+            .storage()
+            .instance()
+            .get(&DataKey::WithdrawalWindow(user.clone()));
+        let accumulated = match window {
+            Some(w) if w.window_index == current_index => w.accumulated + assets,
+            _ => assets,
+        };
+
+        if accumulated > limit {
+            panic!("Withdrawal rate limit exceeded");
+        }
+
+        env.storage().instance().set(
+            &DataKey::WithdrawalWindow(user.clone()),
+            &WithdrawalWindow {
+                window_index: current_index,
+                accumulated,
+            },
+        );
+    }
+
+    /// True if minting shares for `amount` right now would hand the
+    /// depositor back fewer assets than they put in (rounding, or a
+    /// depleted buffer), per PrizeVault's safe-deposit precaution.
+    fn is_lossy_deposit(env: &Env, amount: i128) -> bool {
+        let shares = Self::convert_to_shares(env.clone(), amount);
+        let assets_back = Self::convert_to_assets(env.clone(), shares);
+        assets_back < amount
+    }
+
+    fn assert_owner(env: &Env) {
+        let owner: Address = env.storage().instance().get(&DataKey::Owner).unwrap();
+        owner.require_auth();
+    }
+
+    /// Only the emergency owner or the owner may toggle the pause flags;
+    /// neither can move funds or change limits through this guard.
+    fn assert_emergency_owner(env: &Env, caller: &Address) {
+        caller.require_auth();
+
+        let owner: Address = env.storage().instance().get(&DataKey::Owner).unwrap();
+        let emergency_owner: Address =
+            env.storage().instance().get(&DataKey::EmergencyOwner).unwrap();
+        if *caller != owner && *caller != emergency_owner {
+            panic!("Not authorized: emergency owner required");
+        }
+    }
+}
@@ -1,21 +1,45 @@
 #![cfg(test)]
 
 use super::*;
-use soroban_sdk::{testutils::Address as _, Address, Env};
+use soroban_sdk::{symbol_short, testutils::{Address as _, Ledger as _}, token, Address, Env};
 
 fn setup_vault(env: &Env) -> (Address, Address, Address) {
     let contract_id = env.register_contract(None, NeuroWealthVault);
     let client = NeuroWealthVaultClient::new(env, &contract_id);
-    
+
     let agent = Address::generate(env);
     let usdc_token = Address::generate(env);
     let owner = agent.clone();
-    
+
     client.initialize(&agent, &usdc_token);
-    
+
     (contract_id, agent, owner)
 }
 
+/// Deploys a real USDC-like token and mints `balance` to `holder`, for
+/// tests that need `deposit`/`redeem` to actually move funds.
+fn create_usdc_token(env: &Env, holder: &Address, balance: i128) -> Address {
+    let admin = Address::generate(env);
+    let token_contract = env.register_stellar_asset_contract_v2(admin);
+    let usdc_token = token_contract.address();
+    token::StellarAssetClient::new(env, &usdc_token).mint(holder, &balance);
+    usdc_token
+}
+
+/// Like `setup_vault`, but backed by a real token so deposits succeed.
+/// Returns the vault address alongside the token address, so tests can
+/// mint more of the token straight into the vault to simulate yield.
+fn setup_vault_with_token(env: &Env, holder: &Address, holder_balance: i128) -> (Address, Address) {
+    let contract_id = env.register_contract(None, NeuroWealthVault);
+    let client = NeuroWealthVaultClient::new(env, &contract_id);
+
+    let agent = Address::generate(env);
+    let usdc_token = create_usdc_token(env, holder, holder_balance);
+    client.initialize(&agent, &usdc_token);
+
+    (contract_id, usdc_token)
+}
+
 #[test]
 fn test_get_min_deposit_default() {
     let env = Env::default();
@@ -36,7 +60,8 @@ fn test_get_max_deposit_default() {
     let (contract_id, _agent, _owner) = setup_vault(&env);
     let client = NeuroWealthVaultClient::new(&env, &contract_id);
 
-    let max_deposit = client.get_max_deposit();
+    let receiver = Address::generate(&env);
+    let max_deposit = client.get_max_deposit(&receiver, &0_i128);
     assert_eq!(max_deposit, 10_000_000_000_i128); // 10K USDC default
 }
 
@@ -54,7 +79,8 @@ fn test_set_deposit_limits_success() {
     client.set_deposit_limits(&new_min, &new_max);
 
     assert_eq!(client.get_min_deposit(), new_min);
-    assert_eq!(client.get_max_deposit(), new_max);
+    let receiver = Address::generate(&env);
+    assert_eq!(client.get_max_deposit(&receiver, &0_i128), new_max);
 }
 
 #[test]
@@ -167,7 +193,7 @@ fn test_deposit_at_maximum_succeeds() {
     let amount = 5_000_000_i128; // Exactly at maximum
 
     // This should succeed (though we can't fully test without token mocking)
-    assert_eq!(client.get_max_deposit(), max);
+    assert_eq!(client.get_max_deposit(&_user, &0_i128), max);
     assert!(amount <= max);
 }
 
@@ -218,8 +244,9 @@ fn test_owner_updates_limits_immediate_effect() {
     let client = NeuroWealthVaultClient::new(&env, &contract_id);
 
     // Verify initial limits
+    let receiver = Address::generate(&env);
     assert_eq!(client.get_min_deposit(), 1_000_000_i128);
-    assert_eq!(client.get_max_deposit(), 10_000_000_000_i128);
+    assert_eq!(client.get_max_deposit(&receiver, &0_i128), 10_000_000_000_i128);
 
     // Update limits
     let new_min = 3_000_000_i128; // 3 USDC
@@ -228,7 +255,7 @@ fn test_owner_updates_limits_immediate_effect() {
 
     // Verify new limits are immediately effective
     assert_eq!(client.get_min_deposit(), new_min);
-    assert_eq!(client.get_max_deposit(), new_max);
+    assert_eq!(client.get_max_deposit(&receiver, &0_i128), new_max);
 
     // Test that new limits apply immediately by checking validation
     let _user = Address::generate(&env);
@@ -245,3 +272,563 @@ fn test_owner_updates_limits_immediate_effect() {
     let within_range = 5_000_000_i128; // 5 USDC
     assert!(within_range >= new_min && within_range <= new_max);
 }
+
+#[test]
+fn test_emergency_owner_defaults_to_agent() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract_id, agent, _owner) = setup_vault(&env);
+    let client = NeuroWealthVaultClient::new(&env, &contract_id);
+
+    assert_eq!(client.get_emergency_owner(), agent);
+    assert!(!client.is_deposits_paused());
+    assert!(!client.is_withdrawals_paused());
+}
+
+#[test]
+fn test_set_emergency_owner() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract_id, _agent, _owner) = setup_vault(&env);
+    let client = NeuroWealthVaultClient::new(&env, &contract_id);
+
+    let guardian = Address::generate(&env);
+    client.set_emergency_owner(&guardian);
+
+    assert_eq!(client.get_emergency_owner(), guardian);
+}
+
+#[test]
+fn test_pause_deposits_sets_only_deposit_flag() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract_id, agent, _owner) = setup_vault(&env);
+    let client = NeuroWealthVaultClient::new(&env, &contract_id);
+
+    client.pause_deposits(&agent);
+    assert!(client.is_deposits_paused());
+    assert!(!client.is_withdrawals_paused());
+}
+
+#[test]
+#[should_panic(expected = "Deposits paused")]
+fn test_deposit_reverts_while_paused() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract_id, agent, _owner) = setup_vault(&env);
+    let client = NeuroWealthVaultClient::new(&env, &contract_id);
+
+    client.pause_deposits(&agent);
+
+    let user = Address::generate(&env);
+    client.deposit(&user, &5_000_000_i128);
+}
+
+#[test]
+fn test_resume_clears_both_pause_flags() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract_id, agent, _owner) = setup_vault(&env);
+    let client = NeuroWealthVaultClient::new(&env, &contract_id);
+
+    client.pause_deposits(&agent);
+    client.pause_withdrawals(&agent);
+    assert!(client.is_deposits_paused());
+    assert!(client.is_withdrawals_paused());
+
+    client.resume(&agent);
+    assert!(!client.is_deposits_paused());
+    assert!(!client.is_withdrawals_paused());
+}
+
+#[test]
+fn test_guardian_can_pause_without_owner_key() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract_id, _agent, _owner) = setup_vault(&env);
+    let client = NeuroWealthVaultClient::new(&env, &contract_id);
+
+    let guardian = Address::generate(&env);
+    client.set_emergency_owner(&guardian);
+
+    client.pause_deposits(&guardian);
+    assert!(client.is_deposits_paused());
+}
+
+#[test]
+#[should_panic(expected = "Not authorized: emergency owner required")]
+fn test_pause_deposits_rejects_unauthorized_caller() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract_id, _agent, _owner) = setup_vault(&env);
+    let client = NeuroWealthVaultClient::new(&env, &contract_id);
+
+    let impostor = Address::generate(&env);
+    client.pause_deposits(&impostor);
+}
+
+#[test]
+#[should_panic(expected = "Not authorized: emergency owner required")]
+fn test_pause_withdrawals_rejects_unauthorized_caller() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract_id, _agent, _owner) = setup_vault(&env);
+    let client = NeuroWealthVaultClient::new(&env, &contract_id);
+
+    let impostor = Address::generate(&env);
+    client.pause_withdrawals(&impostor);
+}
+
+#[test]
+#[should_panic(expected = "Not authorized: emergency owner required")]
+fn test_resume_rejects_unauthorized_caller() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract_id, agent, _owner) = setup_vault(&env);
+    let client = NeuroWealthVaultClient::new(&env, &contract_id);
+
+    client.pause_deposits(&agent);
+
+    let impostor = Address::generate(&env);
+    client.resume(&impostor);
+}
+
+#[test]
+fn test_get_max_deposit_clamped_by_total_cap() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract_id, _agent, _owner) = setup_vault(&env);
+    let client = NeuroWealthVaultClient::new(&env, &contract_id);
+
+    client.set_total_deposit_cap(&8_000_000_i128);
+
+    let receiver = Address::generate(&env);
+    // Per-tx max is still 10K USDC, but only 8 USDC of headroom remains.
+    assert_eq!(client.get_max_deposit(&receiver, &0_i128), 8_000_000_i128);
+}
+
+#[test]
+fn test_get_max_deposit_clamped_to_zero_when_cap_exhausted() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract_id, _agent, _owner) = setup_vault(&env);
+    let client = NeuroWealthVaultClient::new(&env, &contract_id);
+
+    // A cap of zero leaves no headroom regardless of the per-tx max.
+    client.set_total_deposit_cap(&0_i128);
+
+    let receiver = Address::generate(&env);
+    assert_eq!(client.get_max_deposit(&receiver, &0_i128), 0_i128);
+}
+
+#[test]
+fn test_deposit_exactly_at_cap_is_within_headroom() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract_id, _agent, _owner) = setup_vault(&env);
+    let client = NeuroWealthVaultClient::new(&env, &contract_id);
+
+    client.set_total_deposit_cap(&5_000_000_i128);
+
+    let receiver = Address::generate(&env);
+    let amount = 5_000_000_i128; // exactly at cap
+
+    // This should succeed (though we can't fully test without token mocking)
+    assert_eq!(client.get_max_deposit(&receiver, &0_i128), amount);
+}
+
+#[test]
+#[should_panic(expected = "Exceeds vault capacity")]
+fn test_deposit_one_stroop_over_cap_reverts() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract_id, _agent, _owner) = setup_vault(&env);
+    let client = NeuroWealthVaultClient::new(&env, &contract_id);
+
+    client.set_total_deposit_cap(&5_000_000_i128);
+
+    let user = Address::generate(&env);
+    // Panics in the cap check before the token transfer is ever attempted.
+    client.deposit(&user, &5_000_001_i128);
+}
+
+#[test]
+fn test_non_increasing_action_skips_cap_check() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract_id, _agent, _owner) = setup_vault(&env);
+    let client = NeuroWealthVaultClient::new(&env, &contract_id);
+
+    // Exhaust the cap entirely...
+    client.set_total_deposit_cap(&0_i128);
+
+    // ...yet a zero/negative delta never increases total assets, so the
+    // cap check is skipped rather than panicking.
+    env.as_contract(&contract_id, || {
+        super::NeuroWealthVault::enforce_total_deposit_cap(&env, 0_i128);
+        super::NeuroWealthVault::enforce_total_deposit_cap(&env, -1_i128);
+    });
+}
+
+#[test]
+fn test_first_depositor_mints_shares_1_to_1() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let user = Address::generate(&env);
+    let (contract_id, _usdc_token) = setup_vault_with_token(&env, &user, 100_000_000_i128);
+    let client = NeuroWealthVaultClient::new(&env, &contract_id);
+
+    let amount = 10_000_000_i128; // 10 USDC
+    let minted = client.deposit(&user, &amount);
+
+    assert_eq!(minted, amount);
+    assert_eq!(client.balance_of(&user), amount);
+    assert_eq!(client.total_assets(), amount);
+}
+
+#[test]
+fn test_proportional_minting_after_assets_change() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let first_user = Address::generate(&env);
+    let (contract_id, usdc_token) = setup_vault_with_token(&env, &first_user, 100_000_000_i128);
+    let client = NeuroWealthVaultClient::new(&env, &contract_id);
+
+    client.deposit(&first_user, &10_000_000_i128); // 10 USDC in -> 10_000_000 shares
+
+    // Yield lands directly in the vault without going through `deposit`,
+    // doubling its assets while total_shares stays put.
+    token::StellarAssetClient::new(&env, &usdc_token).mint(&contract_id, &10_000_000_i128);
+    assert_eq!(client.total_assets(), 20_000_000_i128);
+
+    let second_user = Address::generate(&env);
+    let usdc_for_second_user = 100_000_000_i128;
+    token::StellarAssetClient::new(&env, &usdc_token).mint(&second_user, &usdc_for_second_user);
+
+    // Price per share is now 2 assets/share, so a 10 USDC deposit mints
+    // half as many shares as the first depositor got.
+    let minted = client.deposit(&second_user, &10_000_000_i128);
+    assert_eq!(minted, 5_000_000_i128);
+    assert_eq!(client.balance_of(&second_user), 5_000_000_i128);
+}
+
+#[test]
+#[should_panic(expected = "Lossy deposit")]
+fn test_lossy_deposit_is_rejected() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let first_user = Address::generate(&env);
+    let (contract_id, usdc_token) = setup_vault_with_token(&env, &first_user, 100_000_000_i128);
+    let client = NeuroWealthVaultClient::new(&env, &contract_id);
+
+    client.deposit(&first_user, &3_000_000_i128); // -> 3_000_000 shares at 1:1
+
+    // A single stroop of loss nudges the price per share just off 1:1,
+    // so a min-size deposit now rounds down on the way back to assets.
+    token::Client::new(&env, &usdc_token).transfer(&contract_id, &Address::generate(&env), &1_i128);
+
+    let second_user = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &usdc_token).mint(&second_user, &1_000_000_i128);
+    client.deposit(&second_user, &1_000_000_i128);
+}
+
+#[test]
+fn test_on_deposit_received_credits_shares() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let user = Address::generate(&env);
+    let (contract_id, usdc_token) = setup_vault_with_token(&env, &user, 100_000_000_i128);
+    let client = NeuroWealthVaultClient::new(&env, &contract_id);
+
+    let amount = 5_000_000_i128;
+    // The token contract transfers in before invoking the callback.
+    token::Client::new(&env, &usdc_token).transfer(&user, &contract_id, &amount);
+
+    let unused = client.on_deposit_received(&usdc_token, &user, &amount, &symbol_short!("deposit"));
+
+    assert_eq!(unused, 0);
+    assert_eq!(client.balance_of(&user), amount);
+    assert_eq!(client.total_assets(), amount);
+}
+
+#[test]
+#[should_panic(expected = "Caller must be the configured USDC token")]
+fn test_on_deposit_received_rejects_non_token_caller() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract_id, _agent, _owner) = setup_vault(&env);
+    let client = NeuroWealthVaultClient::new(&env, &contract_id);
+
+    let impostor = Address::generate(&env);
+    let user = Address::generate(&env);
+    client.on_deposit_received(&impostor, &user, &1_000_000_i128, &symbol_short!("deposit"));
+}
+
+#[test]
+fn test_on_deposit_received_refunds_over_maximum() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let user = Address::generate(&env);
+    let (contract_id, usdc_token) = setup_vault_with_token(&env, &user, 100_000_000_000_i128);
+    let client = NeuroWealthVaultClient::new(&env, &contract_id);
+
+    let amount = 20_000_000_000_i128; // over the 10K USDC default max
+    token::Client::new(&env, &usdc_token).transfer(&user, &contract_id, &amount);
+
+    let unused = client.on_deposit_received(&usdc_token, &user, &amount, &symbol_short!("deposit"));
+
+    assert_eq!(unused, amount);
+    assert_eq!(client.balance_of(&user), 0);
+}
+
+#[test]
+fn test_verify_invariants_holds_on_fresh_vault() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let holder = Address::generate(&env);
+    let (contract_id, _usdc_token) = setup_vault_with_token(&env, &holder, 0_i128);
+    let client = NeuroWealthVaultClient::new(&env, &contract_id);
+
+    client.verify_invariants();
+}
+
+#[test]
+fn test_verify_invariants_holds_through_deposit_and_redeem_sequence() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let first_user = Address::generate(&env);
+    let (contract_id, usdc_token) = setup_vault_with_token(&env, &first_user, 100_000_000_i128);
+    let client = NeuroWealthVaultClient::new(&env, &contract_id);
+    client.verify_invariants();
+
+    client.deposit(&first_user, &10_000_000_i128);
+    client.verify_invariants();
+
+    let second_user = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &usdc_token).mint(&second_user, &20_000_000_i128);
+    client.deposit(&second_user, &20_000_000_i128);
+    client.verify_invariants();
+
+    client.redeem(&first_user, &4_000_000_i128);
+    client.verify_invariants();
+
+    client.set_deposit_limits(&2_000_000_i128, &5_000_000_000_i128);
+    client.set_total_deposit_cap(&50_000_000_i128);
+    client.verify_invariants();
+}
+
+#[test]
+#[should_panic(expected = "Invariant violated: max_deposit below min_deposit")]
+fn test_verify_invariants_catches_corrupted_limits() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let holder = Address::generate(&env);
+    let (contract_id, _usdc_token) = setup_vault_with_token(&env, &holder, 0_i128);
+    let client = NeuroWealthVaultClient::new(&env, &contract_id);
+
+    // Bypass the setter's own validation to simulate state that has
+    // drifted into an impossible configuration.
+    env.as_contract(&contract_id, || {
+        env.storage().instance().set(&super::DataKey::MaxDeposit, &0_i128);
+    });
+
+    client.verify_invariants();
+}
+
+#[test]
+#[should_panic(expected = "Invariant violated: sum of share balances does not equal total_shares")]
+fn test_verify_invariants_catches_share_accounting_drift() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let user = Address::generate(&env);
+    let (contract_id, _usdc_token) = setup_vault_with_token(&env, &user, 100_000_000_i128);
+    let client = NeuroWealthVaultClient::new(&env, &contract_id);
+
+    client.deposit(&user, &10_000_000_i128);
+
+    env.as_contract(&contract_id, || {
+        env.storage()
+            .instance()
+            .set(&super::DataKey::TotalShares, &20_000_000_i128);
+    });
+
+    client.verify_invariants();
+}
+
+#[test]
+#[should_panic(expected = "Shares must be positive")]
+fn test_redeem_zero_shares_rejected() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let user = Address::generate(&env);
+    let (contract_id, _usdc_token) = setup_vault_with_token(&env, &user, 100_000_000_i128);
+    let client = NeuroWealthVaultClient::new(&env, &contract_id);
+
+    client.deposit(&user, &10_000_000_i128);
+    client.redeem(&user, &0_i128);
+}
+
+#[test]
+#[should_panic(expected = "Shares must be positive")]
+fn test_redeem_negative_shares_rejected() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let user = Address::generate(&env);
+    let (contract_id, _usdc_token) = setup_vault_with_token(&env, &user, 100_000_000_i128);
+    let client = NeuroWealthVaultClient::new(&env, &contract_id);
+
+    client.deposit(&user, &10_000_000_i128);
+    client.redeem(&user, &-1_000_000_i128);
+}
+
+#[test]
+fn test_redeem_decrements_total_deposited_by_principal_not_yield() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let user = Address::generate(&env);
+    let (contract_id, usdc_token) = setup_vault_with_token(&env, &user, 100_000_000_i128);
+    let client = NeuroWealthVaultClient::new(&env, &contract_id);
+
+    // total_deposited == 10 USDC, exactly at the cap: no headroom left.
+    client.set_total_deposit_cap(&10_000_000_i128);
+    client.deposit(&user, &10_000_000_i128);
+
+    let receiver = Address::generate(&env);
+    assert_eq!(client.get_max_deposit(&receiver, &0_i128), 0_i128);
+
+    // Yield lands directly in the vault without going through `deposit`,
+    // doubling the payout a full redemption returns without touching
+    // total_deposited.
+    token::StellarAssetClient::new(&env, &usdc_token).mint(&contract_id, &10_000_000_i128);
+    let assets = client.redeem(&user, &10_000_000_i128);
+    assert_eq!(assets, 20_000_000_i128);
+
+    // Only the 10 USDC of principal should be backed out, restoring the
+    // full cap as headroom -- not the 20 USDC post-yield payout, which
+    // would double the cap's headroom for the next depositor.
+    assert_eq!(client.get_max_deposit(&receiver, &0_i128), 10_000_000_i128);
+    client.verify_invariants();
+}
+
+#[test]
+fn test_withdrawal_limit_defaults_are_effectively_unlimited() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract_id, _agent, _owner) = setup_vault(&env);
+    let client = NeuroWealthVaultClient::new(&env, &contract_id);
+
+    assert_eq!(client.get_withdrawal_limit_per_window(), i128::MAX);
+    assert_eq!(client.get_withdrawal_window_seconds(), 86_400);
+}
+
+#[test]
+fn test_set_withdrawal_limit() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract_id, _agent, _owner) = setup_vault(&env);
+    let client = NeuroWealthVaultClient::new(&env, &contract_id);
+
+    client.set_withdrawal_limit(&5_000_000_i128, &3_600_u64);
+
+    assert_eq!(client.get_withdrawal_limit_per_window(), 5_000_000_i128);
+    assert_eq!(client.get_withdrawal_window_seconds(), 3_600_u64);
+}
+
+#[test]
+#[should_panic(expected = "Withdrawal limit must be non-negative")]
+fn test_set_withdrawal_limit_rejects_negative_limit() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract_id, _agent, _owner) = setup_vault(&env);
+    let client = NeuroWealthVaultClient::new(&env, &contract_id);
+
+    client.set_withdrawal_limit(&-1_i128, &3_600_u64);
+}
+
+#[test]
+#[should_panic(expected = "Window seconds must be greater than zero")]
+fn test_set_withdrawal_limit_rejects_zero_window() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract_id, _agent, _owner) = setup_vault(&env);
+    let client = NeuroWealthVaultClient::new(&env, &contract_id);
+
+    client.set_withdrawal_limit(&5_000_000_i128, &0_u64);
+}
+
+#[test]
+#[should_panic(expected = "Withdrawal rate limit exceeded")]
+fn test_withdrawal_burst_within_one_window_is_blocked_at_boundary() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let user = Address::generate(&env);
+    let (contract_id, _usdc_token) = setup_vault_with_token(&env, &user, 100_000_000_i128);
+    let client = NeuroWealthVaultClient::new(&env, &contract_id);
+
+    client.set_withdrawal_limit(&7_000_000_i128, &3_600_u64);
+    client.deposit(&user, &10_000_000_i128);
+
+    // First redemption uses up most of the window's allowance...
+    client.redeem(&user, &5_000_000_i128);
+
+    // ...so a second redemption in the same window that would push the
+    // running total past the limit is blocked, even though the user still
+    // has enough shares.
+    client.redeem(&user, &3_000_000_i128);
+}
+
+#[test]
+fn test_withdrawal_accumulator_resets_across_windows() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let user = Address::generate(&env);
+    let (contract_id, _usdc_token) = setup_vault_with_token(&env, &user, 100_000_000_i128);
+    let client = NeuroWealthVaultClient::new(&env, &contract_id);
+
+    client.set_withdrawal_limit(&7_000_000_i128, &3_600_u64);
+    client.deposit(&user, &10_000_000_i128);
+
+    env.ledger().with_mut(|li| li.timestamp = 0);
+    client.redeem(&user, &5_000_000_i128);
+
+    // Advance past the window boundary: the accumulator should have reset,
+    // so a withdrawal that would have been blocked in the old window now
+    // succeeds on its own.
+    env.ledger().with_mut(|li| li.timestamp = 3_600);
+    client.redeem(&user, &3_000_000_i128);
+
+    assert_eq!(client.balance_of(&user), 2_000_000_i128);
+}